@@ -18,6 +18,15 @@ pub struct Cli {
     #[arg(short, long, default_value_t=256)]
     pub page_size: usize,
 
+    #[arg(long, default_value_t=16)]
+    pub address_bits: usize,
+
+    #[arg(long, default_value_t=1)]
+    pub levels: usize,
+
+    #[arg(long, default_value_t=OutputFormat::Text)]
+    pub format: OutputFormat,
+
     #[arg(short, long)]
     pub debug: bool
 }
@@ -27,6 +36,7 @@ pub enum PageReplacementAlgorithm {
     Fifo,
     Lru,
     Opt,
+    Clock,
 }
 
 // Below is boilerplate to allow for strict all caps CLI matching
@@ -36,6 +46,7 @@ impl fmt::Display for PageReplacementAlgorithm {
             PageReplacementAlgorithm::Fifo => "FIFO",
             PageReplacementAlgorithm::Lru => "LRU",
             PageReplacementAlgorithm::Opt => "OPT",
+            PageReplacementAlgorithm::Clock => "CLOCK",
         };
         write!(f, "{}", s)
     }
@@ -43,10 +54,11 @@ impl fmt::Display for PageReplacementAlgorithm {
 
 impl ValueEnum for PageReplacementAlgorithm {
     fn value_variants<'a>() -> &'a [Self] {
-        static VARIANTS: [PageReplacementAlgorithm; 3] = [
+        static VARIANTS: [PageReplacementAlgorithm; 4] = [
             PageReplacementAlgorithm::Fifo,
             PageReplacementAlgorithm::Lru,
             PageReplacementAlgorithm::Opt,
+            PageReplacementAlgorithm::Clock,
         ];
         &VARIANTS
     }
@@ -56,6 +68,40 @@ impl ValueEnum for PageReplacementAlgorithm {
             PageReplacementAlgorithm::Fifo => PossibleValue::new("FIFO"),
             PageReplacementAlgorithm::Lru => PossibleValue::new("LRU"),
             PageReplacementAlgorithm::Opt => PossibleValue::new("OPT"),
+            PageReplacementAlgorithm::Clock => PossibleValue::new("CLOCK"),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: [OutputFormat; 3] = [OutputFormat::Text, OutputFormat::Json, OutputFormat::Csv];
+        &VARIANTS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            OutputFormat::Text => PossibleValue::new("text"),
+            OutputFormat::Json => PossibleValue::new("json"),
+            OutputFormat::Csv => PossibleValue::new("csv"),
         })
     }
 }
\ No newline at end of file
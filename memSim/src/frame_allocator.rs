@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+// Per-resident-frame page-table metadata
+pub struct PageTableEntry {
+    pub insertion_time: usize,
+    pub pn: usize,
+    pub dirty: bool,
+    pub reference_bit: bool,
+}
+
+// Owns the fixed pool of physical frames plus a free list of unused slots,
+// decoupling the replacement policy (which frame to evict) from frame storage
+// (how frames are handed out and recycled).
+pub struct FrameAllocator {
+    frames: Vec<Option<PageTableEntry>>,
+    free_list: VecDeque<usize>,
+}
+
+impl FrameAllocator {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: (0..capacity).map(|_| None).collect(),
+            free_list: (0..capacity).collect(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.free_list.is_empty()
+    }
+
+    // Pops a free frame and installs `entry`, returning its index. Panics if
+    // the pool is full; callers must `free` a victim frame first.
+    pub fn allocate(&mut self, entry: PageTableEntry) -> usize {
+        let idx = self
+            .free_list
+            .pop_front()
+            .expect("no free frames available");
+        self.frames[idx] = Some(entry);
+        idx
+    }
+
+    // Returns a frame to the free list so a future allocation can reuse it
+    pub fn free(&mut self, idx: usize) {
+        self.frames[idx] = None;
+        self.free_list.push_back(idx);
+    }
+
+    pub fn occupied(&self) -> impl Iterator<Item = (usize, &PageTableEntry)> {
+        self.frames
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| entry.as_ref().map(|entry| (idx, entry)))
+    }
+}
+
+impl std::ops::Index<usize> for FrameAllocator {
+    type Output = PageTableEntry;
+
+    fn index(&self, idx: usize) -> &PageTableEntry {
+        self.frames[idx].as_ref().expect("access to unallocated frame")
+    }
+}
+
+impl std::ops::IndexMut<usize> for FrameAllocator {
+    fn index_mut(&mut self, idx: usize) -> &mut PageTableEntry {
+        self.frames[idx].as_mut().expect("access to unallocated frame")
+    }
+}
@@ -1,37 +1,78 @@
 use clap::Parser;
 use std::{
     fs,
-    io::{Read, Seek, SeekFrom},
+    io::{Read, Seek, SeekFrom, Write},
 };
 mod cli;
 use cli::{Cli, PageReplacementAlgorithm};
 mod tlb;
 use tlb::Tlb;
+mod page_table;
+use page_table::PageTable;
+mod frame_allocator;
+use frame_allocator::{FrameAllocator, PageTableEntry};
+mod stats;
+use stats::Stats;
+
+// Cursor state for the Clock algorithm, persisted across eviction calls
+struct ClockState {
+    hand: usize,
+}
 
-struct PageTableEntry {
-    insertion_time: usize,
-    pn: usize,
+// Bundles the state an eviction needs beyond the frames themselves: where to
+// write dirty pages back, the Clock hand cursor, and the run's running counters
+struct EvictionContext<'a> {
+    backing_store: &'a mut fs::File,
+    page_size: usize,
+    dirty_writebacks: &'a mut usize,
+    clock_state: &'a mut ClockState,
+    clock_sweeps: &'a mut usize,
 }
 
 impl PageReplacementAlgorithm {
     fn algorithm(
         &self,
-        ram_frames: &mut Vec<PageTableEntry>,
+        ram_frames: &mut FrameAllocator,
         all_pns: &Vec<usize>,
         present_idx: usize,
+        ctx: &mut EvictionContext,
     ) -> (usize, usize) {
         match self {
-            PageReplacementAlgorithm::Fifo => fifo(ram_frames, all_pns, present_idx),
-            PageReplacementAlgorithm::Lru => lru(ram_frames, all_pns, present_idx),
-            PageReplacementAlgorithm::Opt => opt(ram_frames, all_pns, present_idx),
+            PageReplacementAlgorithm::Fifo => fifo(ram_frames, all_pns, present_idx, ctx),
+            PageReplacementAlgorithm::Lru => lru(ram_frames, all_pns, present_idx, ctx),
+            PageReplacementAlgorithm::Opt => opt(ram_frames, all_pns, present_idx, ctx),
+            PageReplacementAlgorithm::Clock => clock(ram_frames, all_pns, present_idx, ctx),
         }
     }
 }
 
+fn clock(
+    ram_frames: &mut FrameAllocator,
+    all_pns: &Vec<usize>,
+    present_idx: usize,
+    ctx: &mut EvictionContext,
+) -> (usize, usize) {
+    // Walk the hand forward, giving referenced frames a second chance
+    let victim_idx = loop {
+        let idx = ctx.clock_state.hand;
+        ctx.clock_state.hand = (ctx.clock_state.hand + 1) % ram_frames.capacity();
+
+        if ram_frames[idx].reference_bit {
+            ram_frames[idx].reference_bit = false;
+            *ctx.clock_sweeps += 1;
+        } else {
+            break idx;
+        }
+    };
+
+    (victim_idx, eject(ram_frames, all_pns, present_idx, victim_idx, ctx))
+}
+
 fn opt(
-    ram_frames: &mut Vec<PageTableEntry>,
+    ram_frames: &mut FrameAllocator,
     all_pns: &Vec<usize>,
     present_idx: usize,
+    ctx: &mut EvictionContext,
 ) -> (usize, usize) {
     let mut longest_dist: usize = 0;
     let mut longest_pn_idx: usize = 0;
@@ -39,11 +80,11 @@ fn opt(
     // If we're at the final page number
     if present_idx == all_pns.len() - 1 {
         // It doesn't matter who we eject
-        return (0, eject(ram_frames, all_pns, present_idx, 0));
+        return (0, eject(ram_frames, all_pns, present_idx, 0, ctx));
     }
 
     // For each page number in the page table
-    for (idx, entry) in ram_frames.iter().enumerate() {
+    for (idx, entry) in ram_frames.occupied() {
         let mut found: bool = false;
 
         // Find the soonest occurrence of this page number in the future
@@ -69,16 +110,14 @@ fn opt(
         }
     }
 
-    (
-        longest_pn_idx,
-        eject(ram_frames, all_pns, present_idx, longest_pn_idx),
-    )
+    (longest_pn_idx, eject(ram_frames, all_pns, present_idx, longest_pn_idx, ctx))
 }
 
 fn lru(
-    ram_frames: &mut Vec<PageTableEntry>,
+    ram_frames: &mut FrameAllocator,
     all_pns: &Vec<usize>,
     present_idx: usize,
+    ctx: &mut EvictionContext,
 ) -> (usize, usize) {
     let mut longest_dist: usize = 0;
     let mut longest_pn_idx: usize = 0;
@@ -86,11 +125,11 @@ fn lru(
     // If we're at the final page number
     if present_idx == all_pns.len() - 1 {
         // It doesn't matter who we eject
-        return (0, eject(ram_frames, all_pns, present_idx, 0));
+        return (0, eject(ram_frames, all_pns, present_idx, 0, ctx));
     }
 
     // For each page number in the page table
-    for (idx, entry) in ram_frames.iter().enumerate() {
+    for (idx, entry) in ram_frames.occupied() {
         // Find the closest occurrence of this page number in the past
         for (past_idx, pn) in all_pns.iter().enumerate().take(present_idx - 1) {
             if entry.pn == *pn {
@@ -106,22 +145,20 @@ fn lru(
         }
     }
 
-    (
-        longest_pn_idx,
-        eject(ram_frames, all_pns, present_idx, longest_pn_idx),
-    )
+    (longest_pn_idx, eject(ram_frames, all_pns, present_idx, longest_pn_idx, ctx))
 }
 
 fn fifo(
-    ram_frames: &mut Vec<PageTableEntry>,
+    ram_frames: &mut FrameAllocator,
     all_pns: &Vec<usize>,
     present_idx: usize,
+    ctx: &mut EvictionContext,
 ) -> (usize, usize) {
     let mut oldest_time = usize::MAX;
     let mut oldest_idx: usize = 0;
 
     // For each page number in the page table
-    for (idx, entry) in ram_frames.iter().enumerate() {
+    for (idx, entry) in ram_frames.occupied() {
         // Find if this is the oldest page number inserted into the page table
         if entry.insertion_time < oldest_time {
             oldest_time = entry.insertion_time;
@@ -129,10 +166,7 @@ fn fifo(
         }
     }
 
-    (
-        oldest_idx,
-        eject(ram_frames, all_pns, present_idx, oldest_idx),
-    )
+    (oldest_idx, eject(ram_frames, all_pns, present_idx, oldest_idx, ctx))
 }
 
 fn main() {
@@ -140,128 +174,219 @@ fn main() {
     let args = Cli::parse();
 
     let mut tlb = Tlb::new(args.tlb_entries);
-    let mut ram_frames: Vec<PageTableEntry> = Vec::with_capacity(args.frames);
+    let mut ram_frames = FrameAllocator::new(args.frames);
 
-    let mut tlb_misses = 0;
-    let mut page_faults = 0;
+    let mut stats = Stats::new();
+    let mut clock_state = ClockState { hand: 0 };
 
-    let mut backing_store =
-        fs::File::open("BACKING_STORE.bin").expect("Unable to read BACKING_STORE.bin!");
+    // Derive the address decomposition: offset bits come from the page size,
+    // the remaining index bits are split evenly across the page-table levels
+    let offset_bits = args.page_size.trailing_zeros() as usize;
+    let offset_mask = (1usize << offset_bits) - 1;
+
+    if args.levels == 0 {
+        panic!("--levels must be at least 1");
+    }
+    if args.address_bits >= usize::BITS as usize {
+        panic!("--address-bits must be less than {}", usize::BITS);
+    }
+    if args.address_bits < offset_bits {
+        panic!("--address-bits must be at least log2(page-size) ({offset_bits})");
+    }
+    if !(args.address_bits - offset_bits).is_multiple_of(args.levels) {
+        panic!(
+            "--address-bits minus log2(page-size) ({}) must be evenly divisible by --levels ({}); \
+             an uneven split silently drops page-number bits",
+            args.address_bits - offset_bits,
+            args.levels,
+        );
+    }
+
+    let bits_per_level = (args.address_bits - offset_bits) / args.levels;
+    let address_space = 1usize << args.address_bits;
+    let mut page_table = PageTable::new(args.levels, bits_per_level);
+
+    let mut backing_store = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("BACKING_STORE.bin")
+        .expect("Unable to read BACKING_STORE.bin!");
 
     /**********FILE PARSING**********/
     // Get all the the page numbers fromt the file
     let contents = fs::read_to_string(&args.file)
         .unwrap_or_else(|_| panic!("Unable to read file: {}", args.file.display()));
 
-    // Structured as logical address, page number, offset
-    let page_numbers_zipped: Vec<(usize, usize, usize)> = contents
+    // Structured as logical address, page number, offset, is_write
+    let page_numbers_zipped: Vec<(usize, usize, usize, bool)> = contents
         .split('\n')
-        .filter_map(|string| string.parse().ok())
-        .map(|address| (address, (address >> 8usize) & 0xFF, address & 0xFF))
+        .filter_map(parse_reference)
+        .map(|(address, is_write)| {
+            (address, address >> offset_bits, address & offset_mask, is_write)
+        })
         .collect();
 
-    let page_numbers: Vec<usize> = page_numbers_zipped.iter().map(|(_, pn, _)| *pn).collect();
+    let page_numbers: Vec<usize> = page_numbers_zipped.iter().map(|(_, pn, _, _)| *pn).collect();
 
     /**********RUNNING SIMULATOR**********/
     // Check the TLB, if in TLB move on, else look at Page Table
     for (i, page_number) in page_numbers.iter().enumerate() {
+        let (logical_address, _, _, is_write) = page_numbers_zipped[i];
+
+        // An address outside the configured virtual address space is a malformed
+        // reference, not a legitimate page fault; report it and move on
+        if logical_address >= address_space {
+            stats.invalid_page_faults += 1;
+            println!("{}, FAULT, invalid-page", logical_address);
+            continue;
+        }
+
         let mut page_frame_idx = usize::MAX;
 
         if !tlb.contains(*page_number) {
             // If this page number is in RAM but not in TLB
-            if let Some(idx) = ram_frames.iter().position(|entry| entry.pn == *page_number) {
+            if let Some(idx) = page_table.lookup(*page_number) {
                 // We have a soft miss
+                stats.soft_faults += 1;
+                ram_frames[idx].dirty |= is_write;
+                ram_frames[idx].reference_bit = true;
                 if !args.debug {
                     page_frame_idx = idx;
                 }
             } else {
                 // We have a hard miss
-                if ram_frames.len() < ram_frames.capacity() {
+                if !ram_frames.is_full() {
                     // Just fill up normally until full
-                    page_frame_idx = ram_frames.len();
-                    ram_frames.push(PageTableEntry {
+                    page_frame_idx = ram_frames.allocate(PageTableEntry {
                         insertion_time: i,
                         pn: *page_number,
+                        dirty: is_write,
+                        reference_bit: true,
                     });
+                    page_table.insert(*page_number, page_frame_idx);
                 } else {
                     // When full, eject from the page table using strategy
-                    let (frame_idx, ejected_page_number) =
-                        args.pra.algorithm(&mut ram_frames, &page_numbers, i);
+                    let (frame_idx, ejected_page_number) = {
+                        let mut ctx = EvictionContext {
+                            backing_store: &mut backing_store,
+                            page_size: args.page_size,
+                            dirty_writebacks: &mut stats.dirty_writebacks,
+                            clock_state: &mut clock_state,
+                            clock_sweeps: &mut stats.clock_sweeps,
+                        };
+                        args.pra.algorithm(&mut ram_frames, &page_numbers, i, &mut ctx)
+                    };
                     tlb.remove(ejected_page_number);
+                    page_table.remove(ejected_page_number);
+                    page_table.insert(*page_number, frame_idx);
+                    ram_frames[frame_idx].dirty = is_write;
+                    ram_frames[frame_idx].reference_bit = true;
                     page_frame_idx = frame_idx;
+                    stats.evictions += 1;
                 }
 
-                page_faults += 1;
+                stats.hard_faults += 1;
             }
 
             tlb.push(*page_number);
-            tlb_misses += 1;
-        } else if !args.debug {
-            // We have a hit, just get the index
-            if let Some(idx) = ram_frames.iter().position(|entry| entry.pn == *page_number) {
-                page_frame_idx = idx;
+            stats.tlb_misses += 1;
+        } else {
+            stats.tlb_hits += 1;
+            // We have a hit; update the frame regardless of --debug, and only
+            // skip assigning page_frame_idx (used for the printed output)
+            if let Some(idx) = page_table.lookup(*page_number) {
+                ram_frames[idx].dirty |= is_write;
+                ram_frames[idx].reference_bit = true;
+                if !args.debug {
+                    page_frame_idx = idx;
+                }
             }
         }
 
         // Print all the juicy info
-        let (logical_address, _page_number, offset) = page_numbers_zipped[i];
+        let (_, _, offset, _) = page_numbers_zipped[i];
 
-        // Fetch page from backing store at page_number * PAGE_SIZE, print hex
-        if backing_store
+        // Fetch page from backing store at page_number * PAGE_SIZE, print hex.
+        // A seek or short read past the end of the store is a fault, not a
+        // silent no-op: the reference pointed at a page the store doesn't have.
+        let page_read = if backing_store
             .seek(SeekFrom::Start((page_number * args.page_size) as u64))
             .is_ok()
         {
             let mut page = vec![0u8; args.page_size];
-
-            if let Ok(()) = backing_store.read_exact(&mut page) {
-                let value = page[offset] as i8;
-
-                print!("{}, {}, {}, ", logical_address, value, page_frame_idx);
-                if !args.debug {
-                    for byte in &page {
-                        print!("{:02X}", byte);
-                    }
+            backing_store.read_exact(&mut page).ok().map(|_| page)
+        } else {
+            None
+        };
+
+        if let Some(page) = page_read {
+            let value = page[offset] as i8;
+
+            print!("{}, {}, {}, ", logical_address, value, page_frame_idx);
+            if !args.debug {
+                for byte in &page {
+                    print!("{:02X}", byte);
                 }
-                println!();
             }
+            println!();
+        } else {
+            stats.backing_store_faults += 1;
+            println!("{}, FAULT, out-of-bounds-backing-store", logical_address);
         }
     }
 
-    print_statistics(tlb_misses, page_faults, page_numbers.len(), args.debug);
+    stats.addresses = page_numbers.len();
+    stats.print(&args.format, args.debug);
 }
 
 /* HELPER FUNCTIONS */
+
+// Parses a reference-sequence line of the form `R <addr>`, `W <addr>`, or a bare
+// `<addr>` (which defaults to a read), returning the address and whether it's a write.
+fn parse_reference(line: &str) -> Option<(usize, bool)> {
+    let line = line.trim();
+
+    if let Some(rest) = line.strip_prefix("W ") {
+        rest.trim().parse().ok().map(|address| (address, true))
+    } else if let Some(rest) = line.strip_prefix("R ") {
+        rest.trim().parse().ok().map(|address| (address, false))
+    } else {
+        line.parse().ok().map(|address| (address, false))
+    }
+}
+
 fn eject(
-    ram_frames: &mut [PageTableEntry], // Pass a slice instead of a vector
+    ram_frames: &mut FrameAllocator,
     all_pns: &[usize],
     present_idx: usize,
     replace_idx: usize,
+    ctx: &mut EvictionContext,
 ) -> usize {
     let ejected_pn = ram_frames[replace_idx].pn;
-    ram_frames[replace_idx] = PageTableEntry {
+
+    // Write the (simulated) modified page back before the frame is recycled.
+    // The simulator doesn't keep page content in RAM, so round-trip the bytes
+    // actually stored at that offset rather than clobbering them with zeros.
+    if ram_frames[replace_idx].dirty {
+        let offset = (ejected_pn * ctx.page_size) as u64;
+        let mut page = vec![0u8; ctx.page_size];
+
+        if ctx.backing_store.seek(SeekFrom::Start(offset)).is_ok()
+            && ctx.backing_store.read_exact(&mut page).is_ok()
+            && ctx.backing_store.seek(SeekFrom::Start(offset)).is_ok()
+            && ctx.backing_store.write_all(&page).is_ok()
+        {
+            *ctx.dirty_writebacks += 1;
+        }
+    }
+
+    // Free the victim frame, then immediately reallocate it for the incoming page
+    ram_frames.free(replace_idx);
+    ram_frames.allocate(PageTableEntry {
         insertion_time: present_idx,
         pn: all_pns[present_idx],
-    };
+        dirty: false,
+        reference_bit: false,
+    });
     ejected_pn
 }
-
-fn print_statistics(tlb_misses: usize, page_faults: usize, addresses: usize, debug: bool) {
-    /* Prints statistics from TLB and RAM Operations */
-    let hits = addresses - tlb_misses;
-    if debug {
-        println!("***********************************");
-    }
-    println!("Number of Translated Addresses = {}", addresses);
-    println!("Page Faults = {}", page_faults);
-    if !debug {
-        println!(
-            "Page Fault Rate = {:.3}",
-            page_faults as f64 / addresses as f64,
-        );
-    }
-    println!("TLB Hits = {}", hits);
-    println!("TLB Misses = {}", tlb_misses);
-    if !debug {
-        println!("TLB Hit Rate = {:.3}", hits as f64 / addresses as f64,);
-    }
-}
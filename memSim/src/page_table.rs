@@ -0,0 +1,84 @@
+// Array-backed radix tree modeling a multi-level hierarchical page table.
+// Each level holds 2^bits_per_level entries, each either empty, pointing to
+// the next-level table, or a leaf mapping straight to a resident frame.
+enum Entry {
+    Empty,
+    Table(Vec<Entry>),
+    Leaf(usize),
+}
+
+pub struct PageTable {
+    levels: usize,
+    bits_per_level: usize,
+    root: Vec<Entry>,
+}
+
+impl PageTable {
+    pub fn new(levels: usize, bits_per_level: usize) -> Self {
+        Self {
+            levels,
+            bits_per_level,
+            root: Self::new_table(bits_per_level),
+        }
+    }
+
+    fn new_table(bits_per_level: usize) -> Vec<Entry> {
+        (0..1usize << bits_per_level).map(|_| Entry::Empty).collect()
+    }
+
+    // Splits a page number into one index per level, most-significant first
+    fn indices(&self, pn: usize) -> Vec<usize> {
+        let mask = (1usize << self.bits_per_level) - 1;
+        (0..self.levels)
+            .rev()
+            .map(|level| (pn >> (level * self.bits_per_level)) & mask)
+            .collect()
+    }
+
+    pub fn lookup(&self, pn: usize) -> Option<usize> {
+        let indices = self.indices(pn);
+        let mut entry = self.root.get(indices[0])?;
+
+        for &idx in &indices[1..] {
+            match entry {
+                Entry::Table(table) => entry = table.get(idx)?,
+                _ => return None,
+            }
+        }
+
+        match entry {
+            Entry::Leaf(frame) => Some(*frame),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, pn: usize, frame: usize) {
+        let indices = self.indices(pn);
+        let bits_per_level = self.bits_per_level;
+        let mut entry = &mut self.root[indices[0]];
+
+        for &idx in &indices[1..] {
+            if !matches!(entry, Entry::Table(_)) {
+                *entry = Entry::Table(Self::new_table(bits_per_level));
+            }
+            let Entry::Table(table) = entry else { unreachable!() };
+            entry = &mut table[idx];
+        }
+
+        *entry = Entry::Leaf(frame);
+    }
+
+    pub fn remove(&mut self, pn: usize) {
+        let indices = self.indices(pn);
+        let mut entry = &mut self.root[indices[0]];
+
+        for &idx in &indices[1..] {
+            entry = match entry {
+                Entry::Table(table) => &mut table[idx],
+                _ => return,
+            };
+        }
+
+        *entry = Entry::Empty;
+    }
+}
@@ -0,0 +1,114 @@
+use crate::cli::OutputFormat;
+
+// Aggregates every counter the simulator tracks across a run, so the same
+// numbers can be rendered as the usual printed report or as a structured
+// record for batch experiments comparing algorithms across many traces.
+pub struct Stats {
+    pub addresses: usize,
+    pub tlb_hits: usize,
+    pub tlb_misses: usize,
+    pub soft_faults: usize,
+    pub hard_faults: usize,
+    pub evictions: usize,
+    pub dirty_writebacks: usize,
+    pub clock_sweeps: usize,
+    pub invalid_page_faults: usize,
+    pub backing_store_faults: usize,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self {
+            addresses: 0,
+            tlb_hits: 0,
+            tlb_misses: 0,
+            soft_faults: 0,
+            hard_faults: 0,
+            evictions: 0,
+            dirty_writebacks: 0,
+            clock_sweeps: 0,
+            invalid_page_faults: 0,
+            backing_store_faults: 0,
+        }
+    }
+
+    pub fn print(&self, format: &OutputFormat, debug: bool) {
+        match format {
+            OutputFormat::Text => self.print_text(debug),
+            OutputFormat::Json => self.print_json(),
+            OutputFormat::Csv => self.print_csv(),
+        }
+    }
+
+    // References that actually reached the TLB/page table, i.e. everything
+    // except invalid-page addresses rejected before translation began
+    fn translated(&self) -> usize {
+        self.addresses - self.invalid_page_faults
+    }
+
+    fn print_text(&self, debug: bool) {
+        if debug {
+            println!("***********************************");
+        }
+        println!("Number of Translated Addresses = {}", self.translated());
+        println!("Page Faults = {}", self.hard_faults);
+        if !debug {
+            println!(
+                "Page Fault Rate = {:.3}",
+                self.hard_faults as f64 / self.translated() as f64,
+            );
+        }
+        println!("TLB Hits = {}", self.tlb_hits);
+        println!("TLB Misses = {}", self.tlb_misses);
+        if !debug {
+            println!(
+                "TLB Hit Rate = {:.3}",
+                self.tlb_hits as f64 / self.translated() as f64,
+            );
+        }
+        println!("Soft Faults = {}", self.soft_faults);
+        println!("Evictions = {}", self.evictions);
+        println!("Dirty Writebacks = {}", self.dirty_writebacks);
+        println!("Clock Hand Sweeps = {}", self.clock_sweeps);
+        println!("Invalid Page References = {}", self.invalid_page_faults);
+        println!(
+            "Out-of-Bounds Backing Store Accesses = {}",
+            self.backing_store_faults
+        );
+    }
+
+    fn print_json(&self) {
+        println!(
+            "{{\"addresses\":{},\"tlb_hits\":{},\"tlb_misses\":{},\"soft_faults\":{},\"hard_faults\":{},\"evictions\":{},\"dirty_writebacks\":{},\"clock_sweeps\":{},\"invalid_page_faults\":{},\"backing_store_faults\":{}}}",
+            self.addresses,
+            self.tlb_hits,
+            self.tlb_misses,
+            self.soft_faults,
+            self.hard_faults,
+            self.evictions,
+            self.dirty_writebacks,
+            self.clock_sweeps,
+            self.invalid_page_faults,
+            self.backing_store_faults,
+        );
+    }
+
+    fn print_csv(&self) {
+        println!(
+            "addresses,tlb_hits,tlb_misses,soft_faults,hard_faults,evictions,dirty_writebacks,clock_sweeps,invalid_page_faults,backing_store_faults"
+        );
+        println!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.addresses,
+            self.tlb_hits,
+            self.tlb_misses,
+            self.soft_faults,
+            self.hard_faults,
+            self.evictions,
+            self.dirty_writebacks,
+            self.clock_sweeps,
+            self.invalid_page_faults,
+            self.backing_store_faults,
+        );
+    }
+}